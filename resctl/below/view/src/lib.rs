@@ -0,0 +1,41 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::cell::RefCell;
+
+use model::{NetworkModel, SystemModel};
+
+pub mod render;
+pub mod system_view;
+
+use system_view::SystemViewConfig;
+
+/// Shared state stashed on the `Cursive` object and read by every view
+/// module via `c.user_data::<ViewState>()`.
+pub struct ViewState {
+    pub system: RefCell<SystemModel>,
+    pub network: RefCell<NetworkModel>,
+    pub system_view_config: SystemViewConfig,
+    pub cpu_expanded: bool,
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            system: RefCell::new(SystemModel::default()),
+            network: RefCell::new(NetworkModel::default()),
+            system_view_config: SystemViewConfig::load_from_env_or_default(),
+            cpu_expanded: false,
+        }
+    }
+}
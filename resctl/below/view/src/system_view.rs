@@ -11,12 +11,88 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+use std::path::Path;
+
 use cursive::view::{Identifiable, View};
 use cursive::views::{LinearLayout, TextView};
 use cursive::Cursive;
+use serde::Deserialize;
 
 use crate::ViewState;
 
+/// A single configurable row of the system summary view.
+///
+/// `kind` selects which `render_impl::render_*_row` function backs the row.
+/// `fields` only applies to the aggregate rows (`Cpu`, `Mem`, `Vm`) and lets
+/// users override the default `SystemModelFieldId`s shown in that row; it is
+/// ignored for the per-device rows (`Io`, `Iface`, `Gpu`, `GpuMem`, `Temp`),
+/// which always show their one headline field per device.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SystemViewRowConfig {
+    pub kind: SystemViewRowKind,
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemViewRowKind {
+    Cpu,
+    Mem,
+    Vm,
+    Io,
+    Iface,
+    Gpu,
+    GpuMem,
+    Temp,
+}
+
+/// User-selectable configuration for the `system_view` summary rows.
+///
+/// Loaded from a TOML or JSON file and stashed in `ViewState` so `fill_content` can
+/// build the row list dynamically instead of always rendering the fixed
+/// CPU/Mem/VM/I/O/Iface/GPU/Temp set in a fixed order.
+#[derive(Clone, Debug, Deserialize)]
+pub struct SystemViewConfig {
+    pub rows: Vec<SystemViewRowConfig>,
+}
+
+impl Default for SystemViewConfig {
+    fn default() -> Self {
+        use SystemViewRowKind::*;
+        Self {
+            rows: vec![Cpu, Mem, Vm, Io, Iface, Gpu, GpuMem, Temp]
+                .into_iter()
+                .map(|kind| SystemViewRowConfig {
+                    kind,
+                    fields: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl SystemViewConfig {
+    /// Env var pointing at a TOML or JSON file to load the row config from.
+    /// Falls back to `SystemViewConfig::default()` if unset or unreadable.
+    const CONFIG_PATH_ENV: &'static str = "BELOW_SYSTEM_VIEW_CONFIG";
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Ok(serde_json::from_str(&raw)?),
+            _ => Ok(toml::from_str(&raw)?),
+        }
+    }
+
+    pub fn load_from_env_or_default() -> Self {
+        std::env::var_os(Self::CONFIG_PATH_ENV)
+            .map(std::path::PathBuf::from)
+            .and_then(|path| Self::load(&path).ok())
+            .unwrap_or_default()
+    }
+}
+
 mod render_impl {
     use std::collections::BTreeMap;
 
@@ -26,7 +102,10 @@ mod render_impl {
     use crate::render::ViewItem;
 
     use base_render::render_config as rc;
-    use model::{Queriable, SingleDiskModel, SingleNetModel, SystemModel};
+    use model::{
+        Queriable, SingleCpuModel, SingleDiskModel, SingleGpuModel, SingleNetModel,
+        SingleSensorModel, SystemModel,
+    };
 
     /// Renders corresponding Fields From SystemModel.
     type SystemViewItem = ViewItem<model::SystemModelFieldId>;
@@ -101,6 +180,14 @@ mod render_impl {
         render_row("CPU", model, SYS_CPU_ITEMS.iter().cloned())
     }
 
+    /// Expanded form of `render_cpu_row`: one usage column per logical core
+    /// instead of the aggregate Usage/User/System columns, so per-core
+    /// hotspots and imbalance are visible.
+    pub fn render_cpu_row_expanded(cpus: &BTreeMap<String, SingleCpuModel>) -> StyledString {
+        use model::SingleCpuModelFieldId::UsagePct;
+        render_models_row("CPU", cpus.iter(), ViewItem::from_default(UsagePct))
+    }
+
     pub fn render_mem_row(model: &SystemModel) -> StyledString {
         render_row("Mem", model, SYS_MEM_ITEMS.iter().cloned())
     }
@@ -126,6 +213,48 @@ mod render_impl {
             ViewItem::from_default(ThroughputPerSec),
         )
     }
+
+    pub fn render_gpu_row(gpus: &BTreeMap<String, SingleGpuModel>) -> StyledString {
+        use model::SingleGpuModelFieldId::UsagePct;
+        render_models_row("GPU", gpus.iter(), ViewItem::from_default(UsagePct))
+    }
+
+    pub fn render_gpu_mem_row(gpus: &BTreeMap<String, SingleGpuModel>) -> StyledString {
+        use model::SingleGpuModelFieldId::MemoryUsedPct;
+        render_models_row("GPU Mem", gpus.iter(), ViewItem::from_default(MemoryUsedPct))
+    }
+
+    pub fn render_temp_row(sensors: &BTreeMap<String, SingleSensorModel>) -> StyledString {
+        use model::SingleSensorModelFieldId::Temp;
+        render_models_row("Temp", sensors.iter(), ViewItem::from_default(Temp))
+    }
+
+    /// Parses config-supplied field names into `ViewItem`s, or `default` if empty.
+    pub fn parse_items<F>(fields: &[String], default: &Lazy<Vec<ViewItem<F>>>) -> Vec<ViewItem<F>>
+    where
+        F: Clone + std::str::FromStr,
+    {
+        if fields.is_empty() {
+            return default.clone();
+        }
+        fields
+            .iter()
+            .filter_map(|f| F::from_str(f).ok())
+            .map(ViewItem::from_default)
+            .collect()
+    }
+
+    pub fn render_cpu_row_with(model: &SystemModel, fields: &[String]) -> StyledString {
+        render_row("CPU", model, parse_items(fields, &SYS_CPU_ITEMS).into_iter())
+    }
+
+    pub fn render_mem_row_with(model: &SystemModel, fields: &[String]) -> StyledString {
+        render_row("Mem", model, parse_items(fields, &SYS_MEM_ITEMS).into_iter())
+    }
+
+    pub fn render_vm_row_with(model: &SystemModel, fields: &[String]) -> StyledString {
+        render_row("VM", model, parse_items(fields, &SYS_VM_ITEMS).into_iter())
+    }
 }
 
 fn fill_content(c: &mut Cursive, v: &mut LinearLayout) {
@@ -135,32 +264,188 @@ fn fill_content(c: &mut Cursive, v: &mut LinearLayout) {
 
     let system_model = view_state.system.borrow();
     let network_model = view_state.network.borrow();
-    let cpu_row = render_impl::render_cpu_row(&system_model);
-    let mem_row = render_impl::render_mem_row(&system_model);
-    let vm_row = render_impl::render_vm_row(&system_model);
-    let io_row = render_impl::render_io_row(&system_model.disks);
-    let iface_row = render_impl::render_iface_row(&network_model.interfaces);
 
     let mut view = LinearLayout::vertical();
-    view.add_child(TextView::new(cpu_row));
-    view.add_child(TextView::new(mem_row));
-    view.add_child(TextView::new(vm_row));
-    view.add_child(TextView::new(io_row));
-    view.add_child(TextView::new(iface_row));
+    for row in &view_state.system_view_config.rows {
+        let text = match row.kind {
+            SystemViewRowKind::Cpu if view_state.cpu_expanded => {
+                render_impl::render_cpu_row_expanded(&system_model.cpus)
+            }
+            SystemViewRowKind::Cpu => {
+                render_impl::render_cpu_row_with(&system_model, &row.fields)
+            }
+            SystemViewRowKind::Mem => {
+                render_impl::render_mem_row_with(&system_model, &row.fields)
+            }
+            SystemViewRowKind::Vm => render_impl::render_vm_row_with(&system_model, &row.fields),
+            SystemViewRowKind::Io => render_impl::render_io_row(&system_model.disks),
+            SystemViewRowKind::Iface => {
+                render_impl::render_iface_row(&network_model.interfaces)
+            }
+            SystemViewRowKind::Gpu => render_impl::render_gpu_row(&system_model.gpus),
+            SystemViewRowKind::GpuMem => render_impl::render_gpu_mem_row(&system_model.gpus),
+            SystemViewRowKind::Temp => render_impl::render_temp_row(&system_model.sensors),
+        };
+        view.add_child(TextView::new(text));
+    }
 
     *v = view;
 }
 
 pub fn refresh(c: &mut Cursive) {
-    let mut v = c
-        .find_name::<LinearLayout>("system_view")
-        .expect("No system_view view found!");
+    // A no-op when system_view isn't the mounted screen, since refresh can be
+    // triggered (e.g. by the cpu_expanded toggle key) while another view is active.
+    if let Some(mut v) = c.find_name::<LinearLayout>("system_view") {
+        fill_content(c, &mut v);
+    }
+}
 
-    fill_content(c, &mut v);
+/// Toggles between the aggregate and per-core CPU row and redraws.
+pub fn toggle_cpu_expanded(c: &mut Cursive) {
+    let view_state = c
+        .user_data::<ViewState>()
+        .expect("No data stored in Cursive object!");
+    view_state.cpu_expanded = !view_state.cpu_expanded;
+    refresh(c);
 }
 
 pub fn new(c: &mut Cursive) -> impl View {
     let mut view = LinearLayout::vertical();
     fill_content(c, &mut view);
+    c.add_global_callback('C', toggle_cpu_expanded);
     view.with_name("system_view")
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use cursive::utils::markup::StyledString;
+    use serde::{Deserialize, Serialize};
+
+    use model::{NetworkModel, SystemModel};
+
+    use super::render_impl;
+
+    /// A regenerable golden of a rendered row: the plain text plus the style
+    /// applied to each span, so both content and color-coded styling
+    /// (`render_config` thresholds, `get_fixed_width` alignment) are caught
+    /// by a diff.
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+    struct GoldenRow {
+        spans: Vec<GoldenSpan>,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+    struct GoldenSpan {
+        text: String,
+        style: String,
+    }
+
+    fn to_golden(rendered: &StyledString) -> GoldenRow {
+        GoldenRow {
+            spans: rendered
+                .spans()
+                .map(|span| GoldenSpan {
+                    text: span.content.to_string(),
+                    style: format!("{:?}", span.attr),
+                })
+                .collect(),
+        }
+    }
+
+    fn testdata_path(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/system_view")
+            .join(name)
+    }
+
+    fn fixture<T: for<'de> Deserialize<'de>>(name: &str) -> T {
+        let raw = std::fs::read_to_string(testdata_path(name))
+            .unwrap_or_else(|e| panic!("failed to read fixture {}: {}", name, e));
+        serde_json::from_str(&raw).unwrap_or_else(|e| panic!("failed to parse fixture {}: {}", name, e))
+    }
+
+    /// Compares `rendered` against the checked-in golden `name`. Set
+    /// `BELOW_REGENERATE_GOLDEN=1` to overwrite the golden with the current
+    /// render instead of asserting equality.
+    fn assert_matches_golden(name: &str, rendered: &StyledString) {
+        let golden = to_golden(rendered);
+        let path = testdata_path(name);
+        if std::env::var("BELOW_REGENERATE_GOLDEN").is_ok() {
+            std::fs::write(&path, serde_json::to_string_pretty(&golden).unwrap() + "\n").unwrap();
+            return;
+        }
+        let raw = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("failed to read golden {}: {}", name, e));
+        let expected: GoldenRow = serde_json::from_str(&raw).unwrap();
+        assert_eq!(
+            expected, golden,
+            "rendered row does not match golden {}; rerun with BELOW_REGENERATE_GOLDEN=1 to update",
+            name
+        );
+    }
+
+    #[test]
+    fn test_render_cpu_row_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_cpu_row(&system_model);
+        assert_matches_golden("cpu_row.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_mem_row_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_mem_row(&system_model);
+        assert_matches_golden("mem_row.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_vm_row_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_vm_row(&system_model);
+        assert_matches_golden("vm_row.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_io_row_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_io_row(&system_model.disks);
+        assert_matches_golden("io_row.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_iface_row_golden() {
+        let network_model: NetworkModel = fixture("network_model.json");
+        let rendered = render_impl::render_iface_row(&network_model.interfaces);
+        assert_matches_golden("iface_row.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_cpu_row_expanded_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_cpu_row_expanded(&system_model.cpus);
+        assert_matches_golden("cpu_row_expanded.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_gpu_row_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_gpu_row(&system_model.gpus);
+        assert_matches_golden("gpu_row.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_gpu_mem_row_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_gpu_mem_row(&system_model.gpus);
+        assert_matches_golden("gpu_mem_row.golden.json", &rendered);
+    }
+
+    #[test]
+    fn test_render_temp_row_golden() {
+        let system_model: SystemModel = fixture("system_model.json");
+        let rendered = render_impl::render_temp_row(&system_model.sensors);
+        assert_matches_golden("temp_row.golden.json", &rendered);
+    }
+}